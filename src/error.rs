@@ -0,0 +1,132 @@
+//! A structured error type shared across backends.
+//!
+//! Each backend's own `Error` associated type (`JsValue` on [`crate::web`], [`std::io::Error`]
+//! on [`crate::native`], [`crate::memory::Error`] on [`crate::memory`]) is the most natural
+//! representation for that platform, but it means portable code can't match on failure kinds
+//! without depending on a specific backend. [`OpfsError`] gives the [`crate::persistent`] facade
+//! a single error type to classify failures by, while still exposing the backend error's text
+//! through [`std::error::Error::source`] (as a [`BackendError`]; the backend error value itself
+//! is not kept).
+
+use std::fmt;
+
+/// A classification of the underlying backend error, analogous to how VFS implementations
+/// classify inode/path failures rather than leaking OS-specific error codes. Every variant
+/// constructed from a backend error keeps that error's text reachable via
+/// [`std::error::Error::source`] as a [`BackendError`]; the original error value is not retained.
+#[derive(Debug)]
+pub enum OpfsError {
+    /// The entry did not exist.
+    NotFound(BackendError),
+    /// The entry already existed where a caller asked for it not to (e.g. `create_new`).
+    AlreadyExists(BackendError),
+    /// A directory operation was attempted on a file.
+    NotADirectory(BackendError),
+    /// A file operation was attempted on a directory.
+    IsADirectory(BackendError),
+    /// A path was expected to be absolute and wasn't. No backend error converts into this
+    /// variant, so there's no underlying error to preserve.
+    NotAbsolute,
+    /// The storage quota for this origin/filesystem was exceeded.
+    QuotaExceeded(BackendError),
+    /// The given path or name was not valid for this backend.
+    InvalidPath(BackendError),
+    /// A backend error that doesn't map onto any of the above. The original error's text is
+    /// preserved here and remains reachable via [`std::error::Error::source`].
+    Backend(BackendError),
+}
+
+impl fmt::Display for OpfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpfsError::NotFound(_) => write!(f, "no such file or directory"),
+            OpfsError::AlreadyExists(_) => write!(f, "entry already exists"),
+            OpfsError::NotADirectory(_) => write!(f, "not a directory"),
+            OpfsError::IsADirectory(_) => write!(f, "is a directory"),
+            OpfsError::NotAbsolute => write!(f, "path is not absolute"),
+            OpfsError::QuotaExceeded(_) => write!(f, "storage quota exceeded"),
+            OpfsError::InvalidPath(_) => write!(f, "invalid path"),
+            OpfsError::Backend(e) => write!(f, "backend error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for OpfsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OpfsError::NotFound(e)
+            | OpfsError::AlreadyExists(e)
+            | OpfsError::NotADirectory(e)
+            | OpfsError::IsADirectory(e)
+            | OpfsError::QuotaExceeded(e)
+            | OpfsError::InvalidPath(e)
+            | OpfsError::Backend(e) => Some(e),
+            OpfsError::NotAbsolute => None,
+        }
+    }
+}
+
+/// Wraps a backend error's text (its `Display` output for [`std::io::Error`], its `Debug`
+/// output for the other backends) so it can be reached through
+/// [`std::error::Error::source`] even when the backend's own error type (e.g. `JsValue`)
+/// doesn't implement `std::error::Error`.
+#[derive(Debug)]
+pub struct BackendError(String);
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for BackendError {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<std::io::Error> for OpfsError {
+    fn from(err: std::io::Error) -> Self {
+        let backend = BackendError(err.to_string());
+        match err.kind() {
+            std::io::ErrorKind::NotFound => OpfsError::NotFound(backend),
+            std::io::ErrorKind::AlreadyExists => OpfsError::AlreadyExists(backend),
+            std::io::ErrorKind::InvalidInput => OpfsError::InvalidPath(backend),
+            _ => OpfsError::Backend(backend),
+        }
+    }
+}
+
+impl From<crate::memory::Error> for OpfsError {
+    fn from(err: crate::memory::Error) -> Self {
+        let backend = BackendError(format!("{err:?}"));
+        match err {
+            crate::memory::Error::NotFound => OpfsError::NotFound(backend),
+            crate::memory::Error::AlreadyExists => OpfsError::AlreadyExists(backend),
+            crate::memory::Error::NotAFile => OpfsError::IsADirectory(backend),
+            crate::memory::Error::NotADirectory => OpfsError::NotADirectory(backend),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl From<wasm_bindgen::JsValue> for OpfsError {
+    fn from(err: wasm_bindgen::JsValue) -> Self {
+        use wasm_bindgen::JsCast;
+
+        let name = err
+            .dyn_ref::<web_sys::DomException>()
+            .map(|e| e.name())
+            .unwrap_or_default();
+        let backend = BackendError(format!("{err:?}"));
+
+        match name.as_str() {
+            "NotFoundError" => OpfsError::NotFound(backend),
+            "InvalidModificationError" => OpfsError::AlreadyExists(backend),
+            "TypeMismatchError" => OpfsError::NotADirectory(backend),
+            "QuotaExceededError" => OpfsError::QuotaExceeded(backend),
+            "InvalidCharacterError" | "SyntaxError" => OpfsError::InvalidPath(backend),
+            // `NoModificationAllowedError` signals a locked file (e.g. one with an open sync
+            // access handle), not a directory - there's no dedicated variant for that, so it
+            // falls through to `Backend` rather than being misfiled as `IsADirectory`.
+            _ => OpfsError::Backend(backend),
+        }
+    }
+}
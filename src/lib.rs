@@ -47,18 +47,23 @@
 //! - [`web`] - Web platform operations using OPFS APIs
 //! - [`memory`] - In-memory filesystem for use in tests (or when persistence isn't necessary)
 
+mod error;
 pub mod memory;
 pub mod persistent;
 
+pub use error::OpfsError;
+
 #[cfg(target_arch = "wasm32")]
 pub mod web;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod native;
 
-use futures::Stream;
+use futures::{Stream, StreamExt, TryStreamExt};
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::ops::RangeBounds;
+use std::path::PathBuf;
 
 mod private {
     pub trait Sealed {}
@@ -80,6 +85,65 @@ pub struct FileSystemRemoveOptions {
     pub recursive: bool,
 }
 
+/// Builder for opening a file with `std::fs::OpenOptions`-style semantics, in one call that
+/// returns both the opened [`FileHandle`] and a [`WritableFileStream`] positioned according to
+/// the options (e.g. at EOF when `append` is set).
+///
+/// The two semantics that [`GetFileHandleOptions`] and [`CreateWritableOptions`] can't express
+/// on their own are `create_new` (fail if the file already exists) and `append` (always write
+/// at EOF). `create_new` is atomic on `native` and `memory`; on `web` it is an existence check
+/// followed by a separate create.
+#[derive(Debug, Clone, Default)]
+pub struct OpenOptions {
+    pub(crate) read: bool,
+    pub(crate) write: bool,
+    pub(crate) append: bool,
+    pub(crate) truncate: bool,
+    pub(crate) create: bool,
+    pub(crate) create_new: bool,
+}
+
+impl OpenOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read(&mut self, read: bool) -> &mut Self {
+        self.read = read;
+        self
+    }
+
+    pub fn write(&mut self, write: bool) -> &mut Self {
+        self.write = write;
+        self
+    }
+
+    /// Every write goes to EOF, regardless of the stream's seek position.
+    pub fn append(&mut self, append: bool) -> &mut Self {
+        self.append = append;
+        self
+    }
+
+    /// Truncates the file to zero length when opened.
+    pub fn truncate(&mut self, truncate: bool) -> &mut Self {
+        self.truncate = truncate;
+        self
+    }
+
+    /// Creates the file if it doesn't exist.
+    pub fn create(&mut self, create: bool) -> &mut Self {
+        self.create = create;
+        self
+    }
+
+    /// Creates the file, failing with an error if it already exists. Takes precedence over
+    /// `create`.
+    pub fn create_new(&mut self, create_new: bool) -> &mut Self {
+        self.create_new = create_new;
+        self
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum WriteCommandType {
     Write,
@@ -101,6 +165,29 @@ pub enum DirectoryEntry<Directory, File> {
     Directory(Directory),
 }
 
+/// Controls how [`DirectoryHandle::walk`] prunes the tree it traverses.
+pub struct WalkOptions {
+    /// Directories deeper than this (relative to the walk root) are not descended into. `None`
+    /// means unlimited depth.
+    pub max_depth: Option<usize>,
+    /// Called with the name of each sub-directory encountered; returning `false` yields the
+    /// directory entry itself but skips descending into it.
+    pub follow_into: fn(&str) -> bool,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            follow_into: |_| true,
+        }
+    }
+}
+
+/// Chunk size [`DirectoryHandle::export_archive`] reads file payloads in, so exporting a
+/// multi-gigabyte file doesn't buffer it all into one `Vec<u8>`.
+const ARCHIVE_CHUNK_SIZE: usize = 64 * 1024;
+
 pub trait DirectoryHandle: Debug + Sized + private::Sealed {
     type Error: Debug;
     type FileHandleT: FileHandle<Error = Self::Error>;
@@ -117,6 +204,24 @@ pub trait DirectoryHandle: Debug + Sized + private::Sealed {
         options: &GetDirectoryHandleOptions,
     ) -> impl std::future::Future<Output = Result<Self, Self::Error>>;
 
+    /// Opens a file according to `options`, returning it alongside a [`WritableFileStream`]
+    /// already positioned per those options. See [`OpenOptions`] for the semantics this adds
+    /// over [`get_file_handle_with_options`](Self::get_file_handle_with_options).
+    #[allow(clippy::type_complexity)] // not sure how to improve this
+    fn open_with_options(
+        &self,
+        name: &str,
+        options: &OpenOptions,
+    ) -> impl std::future::Future<
+        Output = Result<
+            (
+                Self::FileHandleT,
+                <Self::FileHandleT as FileHandle>::WritableFileStreamT,
+            ),
+            Self::Error,
+        >,
+    >;
+
     fn remove_entry(
         &mut self,
         name: &str,
@@ -137,6 +242,313 @@ pub trait DirectoryHandle: Debug + Sized + private::Sealed {
             Self::Error,
         >,
     >;
+
+    /// Recursively walks every descendant of this directory, breadth-first, yielding each one
+    /// paired with its path relative to `self`. Equivalent to
+    /// `self.walk_with_options(WalkOptions::default())`.
+    #[allow(clippy::type_complexity)] // not sure how to improve this
+    fn walk(
+        &self,
+    ) -> impl std::future::Future<
+        Output = Result<
+            impl Stream<Item = Result<(PathBuf, DirectoryEntry<Self, Self::FileHandleT>), Self::Error>>,
+            Self::Error,
+        >,
+    >
+    where
+        Self: Clone,
+    {
+        self.walk_with_options(WalkOptions::default())
+    }
+
+    /// Like [`walk`](Self::walk), but lets the caller prune the traversal via `options`.
+    ///
+    /// Implemented generically in terms of [`entries`](Self::entries): a work queue of
+    /// not-yet-expanded directories is maintained explicitly and drained breadth-first, so the
+    /// traversal depth never corresponds to Rust call-stack depth. That matters on `web`, whose
+    /// async iterators have no stack to recurse on. Siblings within a directory are sorted by
+    /// name before being yielded, so the traversal order is deterministic across runs rather
+    /// than following `entries()`'s own backend-specific order.
+    #[allow(clippy::type_complexity)] // not sure how to improve this
+    fn walk_with_options(
+        &self,
+        options: WalkOptions,
+    ) -> impl std::future::Future<
+        Output = Result<
+            impl Stream<Item = Result<(PathBuf, DirectoryEntry<Self, Self::FileHandleT>), Self::Error>>,
+            Self::Error,
+        >,
+    >
+    where
+        Self: Clone,
+    {
+        async move {
+            let mut queue = VecDeque::new();
+            queue.push_back((0usize, PathBuf::new(), self.clone()));
+
+            Ok(futures::stream::unfold(
+                (queue, VecDeque::new(), options),
+                |(mut queue, mut buffer, options)| async move {
+                    loop {
+                        if let Some(item) = buffer.pop_front() {
+                            return Some((item, (queue, buffer, options)));
+                        }
+
+                        let (depth, prefix, dir) = queue.pop_front()?;
+                        match dir.entries().await {
+                            Ok(entries) => {
+                                futures::pin_mut!(entries);
+                                let mut siblings = Vec::new();
+                                while let Some(entry) = entries.next().await {
+                                    siblings.push(entry);
+                                }
+                                // `entries()`'s own order is backend-specific (`HashMap`
+                                // iteration on memory, readdir order on native), so sort
+                                // siblings by name here to make traversal order - and anything
+                                // built on it, like `export_archive`'s byte stream - reproducible
+                                // across runs and across backends.
+                                siblings.sort_by(|a, b| match (a, b) {
+                                    (Ok((a_name, _)), Ok((b_name, _))) => a_name.cmp(b_name),
+                                    _ => std::cmp::Ordering::Equal,
+                                });
+                                for entry in siblings {
+                                    match entry {
+                                        Ok((name, DirectoryEntry::File(file))) => {
+                                            buffer.push_back(Ok((
+                                                prefix.join(&name),
+                                                DirectoryEntry::File(file),
+                                            )));
+                                        }
+                                        Ok((name, DirectoryEntry::Directory(subdir))) => {
+                                            let rel_path = prefix.join(&name);
+                                            let within_depth = options
+                                                .max_depth
+                                                .map(|max| depth < max)
+                                                .unwrap_or(true);
+                                            if within_depth && (options.follow_into)(&name) {
+                                                queue.push_back((
+                                                    depth + 1,
+                                                    rel_path.clone(),
+                                                    subdir.clone(),
+                                                ));
+                                            }
+                                            buffer.push_back(Ok((
+                                                rel_path,
+                                                DirectoryEntry::Directory(subdir),
+                                            )));
+                                        }
+                                        Err(e) => buffer.push_back(Err(e)),
+                                    }
+                                }
+                            }
+                            Err(e) => buffer.push_back(Err(e)),
+                        };
+                    }
+                },
+            ))
+        }
+    }
+
+    /// Serializes this directory and every descendant into a single portable stream: a
+    /// sequence of self-describing records (one dir/file tag byte, a `u32` LE path-length, the
+    /// UTF-8 relative path, and for files a `u64` LE payload length followed by the raw bytes,
+    /// itself split across [`ARCHIVE_CHUNK_SIZE`]-sized stream items so a single file's payload
+    /// is never buffered in memory all at once), in the order produced by [`walk`](Self::walk) so
+    /// parents always precede their children. The result can be written anywhere (disk, network,
+    /// another backend's [`import_archive`](Self::import_archive)) to move a whole subtree as one
+    /// blob.
+    fn export_archive(
+        &self,
+    ) -> impl std::future::Future<
+        Output = Result<impl Stream<Item = Result<Vec<u8>, Self::Error>>, Self::Error>,
+    >
+    where
+        Self: Clone,
+        Self::FileHandleT: Clone,
+    {
+        async move {
+            let walk = self.walk().await?;
+            Ok(walk
+                .then(|entry| async move {
+                    let (path, entry) = entry?;
+                    let rel_path = path
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    let path_bytes = rel_path.as_bytes();
+
+                    match entry {
+                        DirectoryEntry::Directory(_) => {
+                            let mut header = Vec::with_capacity(5 + path_bytes.len());
+                            header.push(0u8);
+                            header.extend_from_slice(&(path_bytes.len() as u32).to_le_bytes());
+                            header.extend_from_slice(path_bytes);
+                            Ok(futures::stream::once(async move {
+                                Ok::<Vec<u8>, Self::Error>(header)
+                            })
+                            .left_stream())
+                        }
+                        DirectoryEntry::File(file) => {
+                            let size = file.size().await?;
+                            let mut header = Vec::with_capacity(13 + path_bytes.len());
+                            header.push(1u8);
+                            header.extend_from_slice(&(path_bytes.len() as u32).to_le_bytes());
+                            header.extend_from_slice(path_bytes);
+                            header.extend_from_slice(&(size as u64).to_le_bytes());
+
+                            // Built directly over `read_at` (rather than handing back
+                            // `file.read_stream(..)`) so `file` is moved into the `unfold`
+                            // state instead of borrowed by it - `read_stream`'s stream holds
+                            // a reference to the handle it was called on, which can't outlive
+                            // this `.then` closure's local `file`.
+                            let payload = futures::stream::unfold(
+                                (file, 0usize, size),
+                                move |(handle, offset, size)| async move {
+                                    if offset >= size {
+                                        return None;
+                                    }
+                                    let len = ARCHIVE_CHUNK_SIZE.min(size - offset);
+                                    match handle.read_at(offset, len).await {
+                                        Ok(chunk) => {
+                                            Some((Ok(chunk), (handle, offset + len, size)))
+                                        }
+                                        Err(e) => Some((Err(e), (handle, size, size))),
+                                    }
+                                },
+                            );
+                            Ok(futures::stream::once(async move {
+                                Ok::<Vec<u8>, Self::Error>(header)
+                            })
+                            .chain(payload)
+                            .right_stream())
+                        }
+                    }
+                })
+                .try_flatten())
+        }
+    }
+
+    /// Restores a directory tree previously serialized by [`export_archive`](Self::export_archive),
+    /// creating directory components and files as records are parsed out of `stream`. Record
+    /// headers are buffered just long enough to be parsed, and file payloads are written
+    /// through to the destination as their bytes arrive, so the archive's total size is never
+    /// buffered in memory at once.
+    ///
+    /// If `stream` ends in the middle of a record, the import stops silently rather than
+    /// failing - there's no backend-independent way to report "truncated archive" through
+    /// `Self::Error`.
+    fn import_archive<S>(
+        &mut self,
+        stream: S,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>>
+    where
+        S: Stream<Item = Result<Vec<u8>, Self::Error>>,
+        Self: Clone,
+    {
+        async move {
+            // Bytes before `pos` in `buf` are already-consumed records; `ensure` only compacts
+            // them away once they make up at least half of `buf`, so a stream that delivers the
+            // whole archive as one chunk doesn't cost an O(buffer length) shift per field.
+            async fn ensure<S: Stream<Item = Result<Vec<u8>, E>>, E>(
+                stream: &mut std::pin::Pin<&mut S>,
+                buf: &mut Vec<u8>,
+                pos: &mut usize,
+                n: usize,
+            ) -> Result<bool, E> {
+                while buf.len() - *pos < n {
+                    if *pos > 0 && *pos >= buf.len() / 2 {
+                        buf.drain(..*pos);
+                        *pos = 0;
+                    }
+                    match stream.next().await {
+                        Some(Ok(chunk)) => buf.extend(chunk),
+                        Some(Err(e)) => return Err(e),
+                        None => return Ok(false),
+                    }
+                }
+                Ok(true)
+            }
+
+            futures::pin_mut!(stream);
+            let mut buf: Vec<u8> = Vec::new();
+            let mut pos: usize = 0;
+
+            loop {
+                if !ensure(&mut stream, &mut buf, &mut pos, 1).await? {
+                    break;
+                }
+                let tag = buf[pos];
+                pos += 1;
+
+                if !ensure(&mut stream, &mut buf, &mut pos, 4).await? {
+                    break;
+                }
+                let path_len = u32::from_le_bytes(buf[pos..pos + 4].try_into().unwrap()) as usize;
+                pos += 4;
+
+                if !ensure(&mut stream, &mut buf, &mut pos, path_len).await? {
+                    break;
+                }
+                let path = String::from_utf8_lossy(&buf[pos..pos + path_len]).into_owned();
+                pos += path_len;
+
+                let mut components: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+                let Some(leaf) = components.pop() else {
+                    continue;
+                };
+
+                let mut dir = self.clone();
+                for component in &components {
+                    dir = dir
+                        .get_directory_handle_with_options(
+                            component,
+                            &GetDirectoryHandleOptions { create: true },
+                        )
+                        .await?;
+                }
+
+                if tag == 0 {
+                    dir.get_directory_handle_with_options(
+                        leaf,
+                        &GetDirectoryHandleOptions { create: true },
+                    )
+                    .await?;
+                    continue;
+                }
+
+                if !ensure(&mut stream, &mut buf, &mut pos, 8).await? {
+                    break;
+                }
+                let payload_len = u64::from_le_bytes(buf[pos..pos + 8].try_into().unwrap()) as usize;
+                pos += 8;
+
+                let mut file = dir
+                    .get_file_handle_with_options(leaf, &GetFileHandleOptions { create: true })
+                    .await?;
+                let mut writable = file
+                    .create_writable_with_options(&CreateWritableOptions {
+                        keep_existing_data: false,
+                    })
+                    .await?;
+
+                let mut remaining = payload_len;
+                while remaining > 0 {
+                    if !ensure(&mut stream, &mut buf, &mut pos, 1).await? {
+                        break;
+                    }
+                    let take = remaining.min(buf.len() - pos);
+                    let chunk = buf[pos..pos + take].to_vec();
+                    pos += take;
+                    remaining -= take;
+                    writable.write_at_cursor_pos(chunk).await?;
+                }
+                writable.close().await?;
+            }
+
+            Ok(())
+        }
+    }
 }
 
 pub trait FileHandle: Debug + private::Sealed {
@@ -156,6 +568,140 @@ pub trait FileHandle: Debug + private::Sealed {
     ) -> impl std::future::Future<Output = Result<Vec<u8>, Self::Error>>;
 
     fn size(&self) -> impl std::future::Future<Output = Result<usize, Self::Error>>;
+
+    /// Reads `len` bytes starting at `offset`, independent of any writable stream's cursor.
+    /// Modeled on `FileExt::read_at` (`pread`): concurrent calls don't race over shared
+    /// position state the way a cursor-based API would.
+    fn read_at(
+        &self,
+        offset: usize,
+        len: usize,
+    ) -> impl std::future::Future<Output = Result<Vec<u8>, Self::Error>>;
+
+    /// Writes `data` at `offset`, independent of any writable stream's cursor. Modeled on
+    /// `FileExt::write_at` (`pwrite`).
+    fn write_at(
+        &mut self,
+        offset: usize,
+        data: &[u8],
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>>;
+
+    /// Reads the file in fixed-size chunks instead of buffering it all into one `Vec<u8>`,
+    /// which is fatal for multi-gigabyte OPFS files. Implemented generically on top of
+    /// [`read_at`](Self::read_at), so each chunk is only fetched once the stream is polled for
+    /// it, bounding the caller's memory use to roughly `chunk_size`.
+    fn read_stream(
+        &self,
+        chunk_size: usize,
+    ) -> impl std::future::Future<
+        Output = Result<impl Stream<Item = Result<Vec<u8>, Self::Error>>, Self::Error>,
+    >
+    where
+        Self: Clone,
+    {
+        async move {
+            let size = self.size().await?;
+            Ok(futures::stream::unfold(
+                (self.clone(), 0usize, size),
+                move |(handle, offset, size)| async move {
+                    if offset >= size {
+                        return None;
+                    }
+                    let len = chunk_size.min(size - offset);
+                    match handle.read_at(offset, len).await {
+                        Ok(chunk) => Some((Ok(chunk), (handle, offset + len, size))),
+                        Err(e) => Some((Err(e), (handle, size, size))),
+                    }
+                },
+            ))
+        }
+    }
+
+    /// Splits the file into `n` roughly equal, disjoint byte ranges for data-parallel scans,
+    /// each independently readable via its own clone of this handle (inspired by
+    /// amadeus-core's `File`/`Partition` split). Pass `align_to` to snap boundaries down to a
+    /// record/block size instead of splitting files mid-record.
+    fn partitions(
+        &self,
+        n: usize,
+        align_to: Option<usize>,
+    ) -> impl std::future::Future<Output = Result<Vec<Partition<Self>>, Self::Error>>
+    where
+        Self: Clone,
+    {
+        async move {
+            if n == 0 {
+                return Ok(Vec::new());
+            }
+
+            let size = self.size().await?;
+            let mut boundaries = Vec::with_capacity(n + 1);
+            boundaries.push(0);
+            for i in 1..n {
+                // Computed in 64-bit: on wasm32, where `usize` is 32 bits, `size * i` overflows
+                // for multi-gigabyte files well within this feature's own target use case.
+                let mut boundary = ((size as u64 * i as u64) / n as u64) as usize;
+                if let Some(align) = align_to.filter(|&align| align > 0) {
+                    boundary -= boundary % align;
+                }
+                boundaries.push(boundary);
+            }
+            boundaries.push(size);
+
+            Ok(boundaries
+                .windows(2)
+                .map(|w| Partition {
+                    handle: self.clone(),
+                    start: w[0],
+                    end: w[1],
+                })
+                .collect())
+        }
+    }
+}
+
+/// A disjoint byte range of a [`FileHandle`], independently readable from other partitions of
+/// the same file. See [`FileHandle::partitions`].
+#[derive(Debug, Clone)]
+pub struct Partition<F> {
+    handle: F,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl<F: FileHandle + Clone> Partition<F> {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub async fn read(&self) -> Result<Vec<u8>, F::Error> {
+        self.handle.read_at(self.start, self.len()).await
+    }
+
+    pub async fn read_stream(
+        &self,
+        chunk_size: usize,
+    ) -> Result<impl Stream<Item = Result<Vec<u8>, F::Error>>, F::Error> {
+        let handle = self.handle.clone();
+        let end = self.end;
+        Ok(futures::stream::unfold(
+            (handle, self.start),
+            move |(handle, offset)| async move {
+                if offset >= end {
+                    return None;
+                }
+                let len = chunk_size.min(end - offset);
+                match handle.read_at(offset, len).await {
+                    Ok(chunk) => Some((Ok(chunk), (handle, offset + len))),
+                    Err(e) => Some((Err(e), (handle, end))),
+                }
+            },
+        ))
+    }
 }
 
 pub trait WritableFileStream: Debug + private::Sealed {
@@ -177,4 +723,394 @@ pub trait WritableFileStream: Debug + private::Sealed {
 
     fn seek(&mut self, offset: usize)
     -> impl std::future::Future<Output = Result<(), Self::Error>>;
+
+    /// Writes a stream of chunks sequentially, so callers can pipe a download or a generated
+    /// payload through in bounded memory instead of collecting it into one `Vec<u8>` first.
+    /// Implemented generically on top of [`write_at_cursor_pos`](Self::write_at_cursor_pos).
+    fn write_stream<S>(
+        &mut self,
+        stream: S,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>>
+    where
+        S: Stream<Item = Vec<u8>>,
+    {
+        async move {
+            futures::pin_mut!(stream);
+            while let Some(chunk) = stream.next().await {
+                self.write_at_cursor_pos(chunk).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::DirectoryHandle as MemoryDirectoryHandle;
+
+    async fn write_file(dir: &MemoryDirectoryHandle, name: &str, data: &[u8]) {
+        let mut file = dir
+            .get_file_handle_with_options(name, &GetFileHandleOptions { create: true })
+            .await
+            .unwrap();
+        let mut writable = file
+            .create_writable_with_options(&CreateWritableOptions {
+                keep_existing_data: false,
+            })
+            .await
+            .unwrap();
+        writable.write_at_cursor_pos(data.to_vec()).await.unwrap();
+        writable.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn walk_visits_breadth_first() {
+        let root = MemoryDirectoryHandle::new();
+        write_file(&root, "a.txt", b"a").await;
+        let sub = root
+            .get_directory_handle_with_options("sub", &GetDirectoryHandleOptions { create: true })
+            .await
+            .unwrap();
+        write_file(&sub, "b.txt", b"b").await;
+        let deeper = sub
+            .get_directory_handle_with_options(
+                "deeper",
+                &GetDirectoryHandleOptions { create: true },
+            )
+            .await
+            .unwrap();
+        write_file(&deeper, "c.txt", b"c").await;
+
+        let entries: Vec<_> = root
+            .walk()
+            .await
+            .unwrap()
+            .map(|entry| entry.unwrap().0)
+            .collect()
+            .await;
+
+        let depths: Vec<usize> = entries.iter().map(|p| p.components().count()).collect();
+        assert!(
+            depths.windows(2).all(|w| w[0] <= w[1]),
+            "expected non-decreasing depth as the BFS queue drains: {depths:?}"
+        );
+        assert!(entries.contains(&PathBuf::from("a.txt")));
+        assert!(entries.contains(&PathBuf::from("sub")));
+        assert!(entries.contains(&PathBuf::from("sub/b.txt")));
+        assert!(entries.contains(&PathBuf::from("sub/deeper")));
+        assert!(entries.contains(&PathBuf::from("sub/deeper/c.txt")));
+    }
+
+    #[tokio::test]
+    async fn walk_with_options_respects_max_depth() {
+        let root = MemoryDirectoryHandle::new();
+        let sub = root
+            .get_directory_handle_with_options("sub", &GetDirectoryHandleOptions { create: true })
+            .await
+            .unwrap();
+        let deeper = sub
+            .get_directory_handle_with_options(
+                "deeper",
+                &GetDirectoryHandleOptions { create: true },
+            )
+            .await
+            .unwrap();
+        write_file(&deeper, "c.txt", b"c").await;
+
+        let entries: Vec<_> = root
+            .walk_with_options(WalkOptions {
+                max_depth: Some(1),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .map(|entry| entry.unwrap().0)
+            .collect()
+            .await;
+
+        assert!(entries.contains(&PathBuf::from("sub")));
+        assert!(entries.contains(&PathBuf::from("sub/deeper")));
+        assert!(!entries.contains(&PathBuf::from("sub/deeper/c.txt")));
+    }
+
+    #[tokio::test]
+    async fn partitions_cover_the_whole_file_without_overlap() {
+        let root = MemoryDirectoryHandle::new();
+        let data: Vec<u8> = (0u8..=255).cycle().take(1000).collect();
+        write_file(&root, "big.bin", &data).await;
+        let file = root
+            .get_file_handle_with_options("big.bin", &GetFileHandleOptions { create: false })
+            .await
+            .unwrap();
+
+        let partitions = file.partitions(7, None).await.unwrap();
+
+        assert_eq!(partitions[0].start, 0);
+        assert_eq!(partitions.last().unwrap().end, data.len());
+        for pair in partitions.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start, "partitions must be contiguous");
+        }
+
+        let mut reassembled = Vec::new();
+        for partition in &partitions {
+            reassembled.extend(partition.read().await.unwrap());
+        }
+        assert_eq!(reassembled, data);
+    }
+
+    #[tokio::test]
+    async fn partitions_snap_boundaries_down_to_align_to() {
+        let root = MemoryDirectoryHandle::new();
+        let data = vec![0u8; 100];
+        write_file(&root, "aligned.bin", &data).await;
+        let file = root
+            .get_file_handle_with_options("aligned.bin", &GetFileHandleOptions { create: false })
+            .await
+            .unwrap();
+
+        let partitions = file.partitions(3, Some(16)).await.unwrap();
+
+        for partition in &partitions[..partitions.len() - 1] {
+            assert_eq!(
+                partition.end % 16,
+                0,
+                "every interior boundary should be a multiple of align_to"
+            );
+        }
+        assert_eq!(partitions.last().unwrap().end, data.len());
+    }
+
+    #[tokio::test]
+    async fn partitions_of_zero_yields_no_partitions() {
+        let root = MemoryDirectoryHandle::new();
+        write_file(&root, "big.bin", b"hello").await;
+        let file = root
+            .get_file_handle_with_options("big.bin", &GetFileHandleOptions { create: false })
+            .await
+            .unwrap();
+
+        let partitions = file.partitions(0, None).await.unwrap();
+
+        assert!(partitions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn export_archive_round_trips_through_import_archive() {
+        let source = MemoryDirectoryHandle::new();
+        write_file(&source, "root.txt", b"at the root").await;
+        let sub = source
+            .get_directory_handle_with_options("sub", &GetDirectoryHandleOptions { create: true })
+            .await
+            .unwrap();
+        write_file(&sub, "nested.txt", b"nested contents").await;
+        source
+            .get_directory_handle_with_options(
+                "empty",
+                &GetDirectoryHandleOptions { create: true },
+            )
+            .await
+            .unwrap();
+
+        let archive: Vec<u8> = source
+            .export_archive()
+            .await
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect::<Vec<_>>()
+            .await
+            .concat();
+
+        let mut dest = MemoryDirectoryHandle::new();
+        dest.import_archive(futures::stream::once(async {
+            Ok::<_, crate::memory::Error>(archive)
+        }))
+        .await
+        .unwrap();
+
+        let root_file = dest
+            .get_file_handle_with_options("root.txt", &GetFileHandleOptions { create: false })
+            .await
+            .unwrap();
+        assert_eq!(root_file.read().await.unwrap(), b"at the root");
+
+        let dest_sub = dest
+            .get_directory_handle_with_options("sub", &GetDirectoryHandleOptions { create: false })
+            .await
+            .unwrap();
+        let nested_file = dest_sub
+            .get_file_handle_with_options("nested.txt", &GetFileHandleOptions { create: false })
+            .await
+            .unwrap();
+        assert_eq!(nested_file.read().await.unwrap(), b"nested contents");
+
+        dest.get_directory_handle_with_options("empty", &GetDirectoryHandleOptions { create: false })
+            .await
+            .expect("empty directories should round-trip too");
+    }
+
+    #[tokio::test]
+    async fn open_with_options_create_new_rejects_an_existing_file() {
+        let root = MemoryDirectoryHandle::new();
+        let mut options = OpenOptions::new();
+        options.create_new(true);
+
+        root.open_with_options("exclusive.txt", &options)
+            .await
+            .unwrap();
+
+        let err = root
+            .open_with_options("exclusive.txt", &options)
+            .await
+            .unwrap_err();
+        assert_eq!(err, crate::memory::Error::AlreadyExists);
+    }
+
+    #[tokio::test]
+    async fn open_with_options_append_always_writes_at_the_current_end() {
+        let root = MemoryDirectoryHandle::new();
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+
+        let (file, mut writable) = root.open_with_options("log.txt", &options).await.unwrap();
+        writable.write_at_cursor_pos(b"first;".to_vec()).await.unwrap();
+        // Seek somewhere else entirely; append must ignore this and still land at EOF.
+        writable.seek(0).await.unwrap();
+        writable.write_at_cursor_pos(b"second;".to_vec()).await.unwrap();
+        writable.close().await.unwrap();
+
+        assert_eq!(file.read().await.unwrap(), b"first;second;");
+    }
+
+    #[tokio::test]
+    async fn open_with_options_truncate_empties_an_existing_file() {
+        let root = MemoryDirectoryHandle::new();
+        write_file(&root, "existing.txt", b"stale contents").await;
+
+        let mut options = OpenOptions::new();
+        options.truncate(true);
+        let (file, _writable) = root.open_with_options("existing.txt", &options).await.unwrap();
+
+        assert_eq!(file.size().await.unwrap(), 0);
+    }
+
+    // `OpenOptions`'s whole point is that portable code sees the same behavior on every
+    // backend, but `MemoryDirectoryHandle` ignores `read`/`write` entirely - so the tests above
+    // can't catch a backend that rejects a flag combination std itself is picky about. Cover the
+    // native backend directly for the two options that actually force file creation.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn native_tempdir(label: &str) -> crate::native::DirectoryHandle {
+        let path = std::env::temp_dir().join(format!(
+            "opfs-open-with-options-test-{label}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        crate::native::DirectoryHandle::new(path).await.unwrap()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn native_open_with_options_create_new_rejects_an_existing_file() {
+        let root = native_tempdir("create-new").await;
+        let mut options = OpenOptions::new();
+        options.create_new(true);
+
+        root.open_with_options("exclusive.txt", &options)
+            .await
+            .unwrap();
+
+        let err = root
+            .open_with_options("exclusive.txt", &options)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn native_open_with_options_create_creates_a_new_file() {
+        let root = native_tempdir("create").await;
+        let mut options = OpenOptions::new();
+        options.create(true);
+
+        let (file, _writable) = root.open_with_options("new.txt", &options).await.unwrap();
+        assert_eq!(file.size().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn write_at_past_eof_zero_fills_the_gap() {
+        let root = MemoryDirectoryHandle::new();
+        write_file(&root, "sparse.bin", b"ab").await;
+        let mut file = root
+            .get_file_handle_with_options("sparse.bin", &GetFileHandleOptions { create: false })
+            .await
+            .unwrap();
+
+        file.write_at(5, b"xyz").await.unwrap();
+
+        assert_eq!(file.read().await.unwrap(), b"ab\0\0\0xyz");
+    }
+
+    #[tokio::test]
+    async fn read_at_clamps_a_length_that_runs_past_eof() {
+        let root = MemoryDirectoryHandle::new();
+        write_file(&root, "short.bin", b"hello").await;
+        let file = root
+            .get_file_handle_with_options("short.bin", &GetFileHandleOptions { create: false })
+            .await
+            .unwrap();
+
+        assert_eq!(file.read_at(2, 100).await.unwrap(), b"llo");
+        assert_eq!(file.read_at(100, 10).await.unwrap(), b"");
+    }
+
+    #[tokio::test]
+    async fn read_stream_yields_chunks_up_to_a_non_multiple_chunk_size() {
+        let root = MemoryDirectoryHandle::new();
+        let data: Vec<u8> = (0u8..=255).cycle().take(100).collect();
+        write_file(&root, "chunked.bin", &data).await;
+        let file = root
+            .get_file_handle_with_options("chunked.bin", &GetFileHandleOptions { create: false })
+            .await
+            .unwrap();
+
+        let chunks: Vec<Vec<u8>> = file
+            .read_stream(30)
+            .await
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+
+        let lengths: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(lengths, vec![30, 30, 30, 10], "last chunk should be the remainder");
+        assert_eq!(chunks.concat(), data);
+    }
+
+    #[tokio::test]
+    async fn write_stream_writes_chunks_sequentially() {
+        let root = MemoryDirectoryHandle::new();
+        let mut file = root
+            .get_file_handle_with_options("streamed.bin", &GetFileHandleOptions { create: true })
+            .await
+            .unwrap();
+        let mut writable = file
+            .create_writable_with_options(&CreateWritableOptions {
+                keep_existing_data: false,
+            })
+            .await
+            .unwrap();
+
+        let chunks = vec![b"abc".to_vec(), b"de".to_vec(), b"fghij".to_vec()];
+        writable
+            .write_stream(futures::stream::iter(chunks))
+            .await
+            .unwrap();
+        writable.close().await.unwrap();
+
+        assert_eq!(file.read().await.unwrap(), b"abcdefghij");
+    }
 }
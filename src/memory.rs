@@ -0,0 +1,340 @@
+//! In-memory filesystem for use in tests (or when persistence isn't necessary).
+//!
+//! This backend keeps its entire tree in memory behind `Arc<RwLock<_>>` handles, so cloning a
+//! [`DirectoryHandle`] or [`FileHandle`] gives you another reference to the same underlying
+//! data, matching the sharing semantics of the browser's OPFS handles.
+
+use futures::Stream;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+use std::sync::{Arc, RwLock};
+
+type DirectoryEntry = crate::DirectoryEntry<DirectoryHandle, FileHandle>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    NotFound,
+    AlreadyExists,
+    NotAFile,
+    NotADirectory,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound => write!(f, "entry not found"),
+            Error::AlreadyExists => write!(f, "entry already exists"),
+            Error::NotAFile => write!(f, "entry is not a file"),
+            Error::NotADirectory => write!(f, "entry is not a directory"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Clone)]
+enum Node {
+    File(Arc<RwLock<Vec<u8>>>),
+    Directory(Arc<RwLock<HashMap<String, Node>>>),
+}
+
+#[derive(Debug, Clone)]
+pub struct DirectoryHandle(Arc<RwLock<HashMap<String, Node>>>);
+
+#[derive(Debug, Clone)]
+pub struct FileHandle(Arc<RwLock<Vec<u8>>>);
+
+#[derive(Debug)]
+pub struct WritableFileStream {
+    file: Arc<RwLock<Vec<u8>>>,
+    keep_existing_data: bool,
+    cursor: usize,
+    /// When set, `write_at_cursor_pos` re-seeks to the file's current length before every write,
+    /// so append mode keeps appending even if something else grows the file between writes.
+    append: bool,
+}
+
+impl Default for DirectoryHandle {
+    fn default() -> Self {
+        Self(Arc::new(RwLock::new(HashMap::new())))
+    }
+}
+
+impl DirectoryHandle {
+    /// Creates a new, empty in-memory root directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl crate::private::Sealed for DirectoryHandle {}
+impl crate::private::Sealed for FileHandle {}
+impl crate::private::Sealed for WritableFileStream {}
+
+impl crate::DirectoryHandle for DirectoryHandle {
+    type Error = Error;
+    type FileHandleT = FileHandle;
+
+    async fn get_file_handle_with_options(
+        &self,
+        name: &str,
+        options: &crate::GetFileHandleOptions,
+    ) -> Result<Self::FileHandleT, Self::Error> {
+        let mut entries = self.0.write().unwrap();
+        match entries.get(name) {
+            Some(Node::File(file)) => Ok(FileHandle(file.clone())),
+            Some(Node::Directory(_)) => Err(Error::NotAFile),
+            None if options.create => {
+                let file = Arc::new(RwLock::new(Vec::new()));
+                entries.insert(name.to_string(), Node::File(file.clone()));
+                Ok(FileHandle(file))
+            }
+            None => Err(Error::NotFound),
+        }
+    }
+
+    async fn open_with_options(
+        &self,
+        name: &str,
+        options: &crate::OpenOptions,
+    ) -> Result<(FileHandle, WritableFileStream), Self::Error> {
+        let file = {
+            let mut entries = self.0.write().unwrap();
+            match entries.get(name) {
+                Some(Node::File(_)) if options.create_new => return Err(Error::AlreadyExists),
+                Some(Node::File(file)) => file.clone(),
+                Some(Node::Directory(_)) => return Err(Error::NotAFile),
+                None if options.create || options.create_new => {
+                    let file = Arc::new(RwLock::new(Vec::new()));
+                    entries.insert(name.to_string(), Node::File(file.clone()));
+                    file
+                }
+                None => return Err(Error::NotFound),
+            }
+        };
+
+        if options.truncate {
+            file.write().unwrap().clear();
+        }
+        let cursor = if options.append {
+            file.read().unwrap().len()
+        } else {
+            0
+        };
+
+        Ok((
+            FileHandle(file.clone()),
+            WritableFileStream {
+                file,
+                keep_existing_data: !options.truncate,
+                cursor,
+                append: options.append,
+            },
+        ))
+    }
+
+    async fn get_directory_handle_with_options(
+        &self,
+        name: &str,
+        options: &crate::GetDirectoryHandleOptions,
+    ) -> Result<Self, Self::Error> {
+        let mut entries = self.0.write().unwrap();
+        match entries.get(name) {
+            Some(Node::Directory(dir)) => Ok(DirectoryHandle(dir.clone())),
+            Some(Node::File(_)) => Err(Error::NotADirectory),
+            None if options.create => {
+                let dir = Arc::new(RwLock::new(HashMap::new()));
+                entries.insert(name.to_string(), Node::Directory(dir.clone()));
+                Ok(DirectoryHandle(dir))
+            }
+            None => Err(Error::NotFound),
+        }
+    }
+
+    async fn remove_entry(&mut self, name: &str) -> Result<(), Self::Error> {
+        let mut entries = self.0.write().unwrap();
+        match entries.get(name) {
+            Some(Node::Directory(dir)) if !dir.read().unwrap().is_empty() => {
+                Err(Error::NotAFile)
+            }
+            Some(_) => {
+                entries.remove(name);
+                Ok(())
+            }
+            None => Err(Error::NotFound),
+        }
+    }
+
+    async fn remove_entry_with_options(
+        &mut self,
+        name: &str,
+        options: &crate::FileSystemRemoveOptions,
+    ) -> Result<(), Self::Error> {
+        let mut entries = self.0.write().unwrap();
+        match entries.get(name) {
+            Some(Node::Directory(dir)) if !options.recursive && !dir.read().unwrap().is_empty() => {
+                Err(Error::NotAFile)
+            }
+            Some(_) => {
+                entries.remove(name);
+                Ok(())
+            }
+            None => Err(Error::NotFound),
+        }
+    }
+
+    async fn entries(
+        &self,
+    ) -> Result<impl Stream<Item = Result<(String, DirectoryEntry), Self::Error>>, Self::Error>
+    {
+        let entries = self.0.read().unwrap();
+        let items: Vec<_> = entries
+            .iter()
+            .map(|(name, node)| {
+                let entry = match node {
+                    Node::File(file) => DirectoryEntry::File(FileHandle(file.clone())),
+                    Node::Directory(dir) => DirectoryEntry::Directory(DirectoryHandle(dir.clone())),
+                };
+                Ok((name.clone(), entry))
+            })
+            .collect();
+        Ok(futures::stream::iter(items))
+    }
+}
+
+impl crate::FileHandle for FileHandle {
+    type Error = Error;
+    type WritableFileStreamT = WritableFileStream;
+
+    async fn create_writable_with_options(
+        &mut self,
+        options: &crate::CreateWritableOptions,
+    ) -> Result<Self::WritableFileStreamT, Self::Error> {
+        if !options.keep_existing_data {
+            self.0.write().unwrap().clear();
+        }
+        Ok(WritableFileStream {
+            file: self.0.clone(),
+            keep_existing_data: options.keep_existing_data,
+            cursor: 0,
+            append: false,
+        })
+    }
+
+    async fn read(&self) -> Result<Vec<u8>, Self::Error> {
+        Ok(self.0.read().unwrap().clone())
+    }
+
+    async fn read_range<R: RangeBounds<usize> + Send>(
+        &self,
+        range: R,
+    ) -> Result<Vec<u8>, Self::Error> {
+        let data = self.0.read().unwrap();
+        let size = data.len();
+
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => size,
+        };
+
+        if start >= size {
+            return Ok(Vec::new());
+        }
+        let end = end.min(size);
+        if start >= end {
+            return Ok(Vec::new());
+        }
+        Ok(data[start..end].to_vec())
+    }
+
+    async fn size(&self) -> Result<usize, Self::Error> {
+        Ok(self.0.read().unwrap().len())
+    }
+
+    async fn read_at(&self, offset: usize, len: usize) -> Result<Vec<u8>, Self::Error> {
+        let data = self.0.read().unwrap();
+        if offset >= data.len() {
+            return Ok(Vec::new());
+        }
+        let end = (offset + len).min(data.len());
+        Ok(data[offset..end].to_vec())
+    }
+
+    async fn write_at(&mut self, offset: usize, data: &[u8]) -> Result<(), Self::Error> {
+        let mut file = self.0.write().unwrap();
+        let end = offset + data.len();
+        if file.len() < end {
+            file.resize(end, 0);
+        }
+        file[offset..end].copy_from_slice(data);
+        Ok(())
+    }
+}
+
+impl crate::WritableFileStream for WritableFileStream {
+    type Error = Error;
+
+    async fn write_at_cursor_pos(&mut self, data: Vec<u8>) -> Result<(), Self::Error> {
+        let mut file = self.file.write().unwrap();
+        if self.append {
+            self.cursor = file.len();
+        }
+        let end = self.cursor + data.len();
+        if file.len() < end {
+            file.resize(end, 0);
+        }
+        file[self.cursor..end].copy_from_slice(&data);
+        self.cursor = end;
+        Ok(())
+    }
+
+    async fn write_with_params(&mut self, params: &crate::WriteParams) -> Result<(), Self::Error> {
+        use crate::WriteCommandType;
+
+        match params.command_type {
+            WriteCommandType::Write => {
+                if let Some(position) = params.position {
+                    self.cursor = position;
+                }
+                if let Some(data) = params.data.clone() {
+                    self.write_at_cursor_pos(data).await?;
+                }
+            }
+            WriteCommandType::Seek => {
+                if let Some(position) = params.position {
+                    self.cursor = position;
+                }
+            }
+            WriteCommandType::Truncate => {
+                if let Some(size) = params.size {
+                    self.file.write().unwrap().resize(size, 0);
+                    self.cursor = self.cursor.min(size);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn truncate(&mut self, size: usize) -> Result<(), Self::Error> {
+        self.file.write().unwrap().resize(size, 0);
+        self.cursor = self.cursor.min(size);
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        let _ = self.keep_existing_data;
+        Ok(())
+    }
+
+    async fn seek(&mut self, offset: usize) -> Result<(), Self::Error> {
+        self.cursor = offset;
+        Ok(())
+    }
+}
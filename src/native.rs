@@ -0,0 +1,378 @@
+//! Native file system operations using `tokio::fs`.
+//!
+//! This backend maps the [`crate::DirectoryHandle`], [`crate::FileHandle`], and
+//! [`crate::WritableFileStream`] traits onto ordinary paths on disk, so that code written
+//! against those traits behaves the same whether it runs in a browser (via [`crate::web`]) or
+//! natively.
+
+use futures::Stream;
+use std::fmt::Debug;
+use std::io;
+use std::ops::{Bound, RangeBounds};
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+type DirectoryEntry = crate::DirectoryEntry<DirectoryHandle, FileHandle>;
+
+#[derive(Debug, Clone)]
+pub struct DirectoryHandle(PathBuf);
+
+#[derive(Debug, Clone)]
+pub struct FileHandle(PathBuf);
+
+#[derive(Debug)]
+pub struct WritableFileStream(tokio::fs::File);
+
+impl From<PathBuf> for DirectoryHandle {
+    fn from(path: PathBuf) -> Self {
+        Self(path)
+    }
+}
+
+impl From<PathBuf> for FileHandle {
+    fn from(path: PathBuf) -> Self {
+        Self(path)
+    }
+}
+
+impl DirectoryHandle {
+    /// Creates a handle rooted at an arbitrary path on disk, creating the directory if it
+    /// doesn't already exist.
+    pub async fn new(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        fs::create_dir_all(&path).await?;
+        Ok(Self(path))
+    }
+
+    /// The path on disk this handle is rooted at.
+    pub fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+impl FileHandle {
+    /// The path on disk this handle refers to.
+    pub fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+impl crate::private::Sealed for DirectoryHandle {}
+impl crate::private::Sealed for FileHandle {}
+impl crate::private::Sealed for WritableFileStream {}
+
+impl crate::DirectoryHandle for DirectoryHandle {
+    type Error = io::Error;
+    type FileHandleT = FileHandle;
+
+    async fn get_file_handle_with_options(
+        &self,
+        name: &str,
+        options: &crate::GetFileHandleOptions,
+    ) -> Result<Self::FileHandleT, Self::Error> {
+        let path = self.0.join(name);
+        if options.create {
+            let _ = fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(false)
+                .open(&path)
+                .await?;
+        } else {
+            fs::metadata(&path).await?;
+        }
+        Ok(FileHandle(path))
+    }
+
+    async fn open_with_options(
+        &self,
+        name: &str,
+        options: &crate::OpenOptions,
+    ) -> Result<(FileHandle, WritableFileStream), Self::Error> {
+        let path = self.0.join(name);
+        let file = fs::OpenOptions::new()
+            .read(options.read)
+            // std rejects `truncate`/`create`/`create_new` unless `write` is also set, but
+            // memory and web all apply those regardless of `options.write` - so each of them
+            // implies write here too, to keep the three backends agreeing on what `OpenOptions`
+            // alone describes.
+            .write(
+                options.write
+                    || options.append
+                    || options.truncate
+                    || options.create
+                    || options.create_new,
+            )
+            .append(options.append)
+            .truncate(options.truncate)
+            .create(options.create)
+            .create_new(options.create_new)
+            .open(&path)
+            .await?;
+        Ok((FileHandle(path), WritableFileStream(file)))
+    }
+
+    async fn get_directory_handle_with_options(
+        &self,
+        name: &str,
+        options: &crate::GetDirectoryHandleOptions,
+    ) -> Result<Self, Self::Error> {
+        let path = self.0.join(name);
+        if options.create {
+            fs::create_dir_all(&path).await?;
+        } else {
+            let metadata = fs::metadata(&path).await?;
+            if !metadata.is_dir() {
+                return Err(io::Error::other(format!(
+                    "{} is not a directory",
+                    path.display()
+                )));
+            }
+        }
+        Ok(DirectoryHandle(path))
+    }
+
+    async fn remove_entry(&mut self, name: &str) -> Result<(), Self::Error> {
+        let path = self.0.join(name);
+        if fs::metadata(&path).await?.is_dir() {
+            fs::remove_dir(&path).await
+        } else {
+            fs::remove_file(&path).await
+        }
+    }
+
+    async fn remove_entry_with_options(
+        &mut self,
+        name: &str,
+        options: &crate::FileSystemRemoveOptions,
+    ) -> Result<(), Self::Error> {
+        let path = self.0.join(name);
+        if fs::metadata(&path).await?.is_dir() {
+            if options.recursive {
+                fs::remove_dir_all(&path).await
+            } else {
+                fs::remove_dir(&path).await
+            }
+        } else {
+            fs::remove_file(&path).await
+        }
+    }
+
+    async fn entries(
+        &self,
+    ) -> Result<impl Stream<Item = Result<(String, DirectoryEntry), Self::Error>>, Self::Error>
+    {
+        let mut read_dir = fs::read_dir(&self.0).await?;
+        let mut items = Vec::new();
+        loop {
+            let entry = match read_dir.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(e) => {
+                    items.push(Err(e));
+                    break;
+                }
+            };
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = entry.path();
+            let item = match entry.file_type().await {
+                Ok(file_type) if file_type.is_dir() => {
+                    Ok((name, DirectoryEntry::Directory(DirectoryHandle(path))))
+                }
+                Ok(_) => Ok((name, DirectoryEntry::File(FileHandle(path)))),
+                Err(e) => Err(e),
+            };
+            items.push(item);
+        }
+        Ok(futures::stream::iter(items))
+    }
+}
+
+impl crate::FileHandle for FileHandle {
+    type Error = io::Error;
+    type WritableFileStreamT = WritableFileStream;
+
+    async fn create_writable_with_options(
+        &mut self,
+        options: &crate::CreateWritableOptions,
+    ) -> Result<Self::WritableFileStreamT, Self::Error> {
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(!options.keep_existing_data)
+            .open(&self.0)
+            .await?;
+        Ok(WritableFileStream(file))
+    }
+
+    async fn read(&self) -> Result<Vec<u8>, Self::Error> {
+        fs::read(&self.0).await
+    }
+
+    async fn read_range<R: RangeBounds<usize> + Send>(
+        &self,
+        range: R,
+    ) -> Result<Vec<u8>, Self::Error> {
+        let size = self.size().await?;
+
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => size,
+        };
+
+        if start >= size {
+            return Ok(Vec::new());
+        }
+        let end = end.min(size);
+        if start >= end {
+            return Ok(Vec::new());
+        }
+
+        let mut file = fs::File::open(&self.0).await?;
+        file.seek(io::SeekFrom::Start(start as u64)).await?;
+        let mut buf = vec![0u8; end - start];
+        file.read_exact(&mut buf).await?;
+        Ok(buf)
+    }
+
+    async fn size(&self) -> Result<usize, Self::Error> {
+        let metadata = fs::metadata(&self.0).await?;
+        Ok(metadata.len() as usize)
+    }
+
+    #[cfg(unix)]
+    async fn read_at(&self, offset: usize, len: usize) -> Result<Vec<u8>, Self::Error> {
+        use std::os::unix::fs::FileExt;
+
+        let path = self.0.clone();
+        tokio::task::spawn_blocking(move || {
+            let file = std::fs::File::open(&path)?;
+            // Clamp to the file's current size rather than `read_exact_at`-ing the full `len`:
+            // memory and web both return a short tail for a length that runs past EOF, and this
+            // backend needs to agree with them instead of erroring with `UnexpectedEof`.
+            let size = file.metadata()?.len() as usize;
+            let len = len.min(size.saturating_sub(offset));
+            let mut buf = vec![0u8; len];
+            let mut total = 0;
+            while total < len {
+                match file.read_at(&mut buf[total..], (offset + total) as u64)? {
+                    0 => break,
+                    n => total += n,
+                }
+            }
+            buf.truncate(total);
+            Ok(buf)
+        })
+        .await
+        .unwrap_or_else(|e| Err(io::Error::other(e)))
+    }
+
+    #[cfg(unix)]
+    async fn write_at(&mut self, offset: usize, data: &[u8]) -> Result<(), Self::Error> {
+        use std::os::unix::fs::FileExt;
+
+        let path = self.0.clone();
+        let data = data.to_vec();
+        tokio::task::spawn_blocking(move || {
+            let file = std::fs::OpenOptions::new().write(true).open(&path)?;
+            file.write_all_at(&data, offset as u64)
+        })
+        .await
+        .unwrap_or_else(|e| Err(io::Error::other(e)))
+    }
+
+    #[cfg(windows)]
+    async fn read_at(&self, offset: usize, len: usize) -> Result<Vec<u8>, Self::Error> {
+        use std::os::windows::fs::FileExt;
+
+        let path = self.0.clone();
+        tokio::task::spawn_blocking(move || {
+            let file = std::fs::File::open(&path)?;
+            let mut buf = vec![0u8; len];
+            let mut total = 0;
+            while total < len {
+                match file.seek_read(&mut buf[total..], (offset + total) as u64)? {
+                    0 => break,
+                    n => total += n,
+                }
+            }
+            buf.truncate(total);
+            Ok(buf)
+        })
+        .await
+        .unwrap_or_else(|e| Err(io::Error::other(e)))
+    }
+
+    #[cfg(windows)]
+    async fn write_at(&mut self, offset: usize, data: &[u8]) -> Result<(), Self::Error> {
+        use std::os::windows::fs::FileExt;
+
+        let path = self.0.clone();
+        let data = data.to_vec();
+        tokio::task::spawn_blocking(move || {
+            let file = std::fs::OpenOptions::new().write(true).open(&path)?;
+            let mut total = 0;
+            while total < data.len() {
+                total += file.seek_write(&data[total..], (offset + total) as u64)?;
+            }
+            Ok(())
+        })
+        .await
+        .unwrap_or_else(|e| Err(io::Error::other(e)))
+    }
+}
+
+impl crate::WritableFileStream for WritableFileStream {
+    type Error = io::Error;
+
+    async fn write_at_cursor_pos(&mut self, data: Vec<u8>) -> Result<(), Self::Error> {
+        self.0.write_all(&data).await
+    }
+
+    async fn write_with_params(&mut self, params: &crate::WriteParams) -> Result<(), Self::Error> {
+        use crate::WriteCommandType;
+
+        match params.command_type {
+            WriteCommandType::Write => {
+                if let Some(position) = params.position {
+                    self.0.seek(io::SeekFrom::Start(position as u64)).await?;
+                }
+                if let Some(data) = &params.data {
+                    self.0.write_all(data).await?;
+                }
+            }
+            WriteCommandType::Seek => {
+                if let Some(position) = params.position {
+                    self.0.seek(io::SeekFrom::Start(position as u64)).await?;
+                }
+            }
+            WriteCommandType::Truncate => {
+                if let Some(size) = params.size {
+                    self.0.set_len(size as u64).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn truncate(&mut self, size: usize) -> Result<(), Self::Error> {
+        self.0.set_len(size as u64).await
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.0.flush().await?;
+        self.0.sync_all().await
+    }
+
+    async fn seek(&mut self, offset: usize) -> Result<(), Self::Error> {
+        self.0.seek(io::SeekFrom::Start(offset as u64)).await?;
+        Ok(())
+    }
+}
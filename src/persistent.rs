@@ -0,0 +1,198 @@
+//! Platform-agnostic types that automatically resolve to the correct backend: [`crate::web`]
+//! when compiled for `wasm32`, [`crate::native`] everywhere else.
+//!
+//! Import from this module (rather than `web` or `native` directly) when you want code that
+//! compiles and runs the same way on both platforms. Unlike the backends, whose `Error` type is
+//! whatever is most natural for that platform (`JsValue`, `std::io::Error`, ...), this facade's
+//! `Error` is always [`OpfsError`], so portable code can match on failure kinds.
+
+#[cfg(target_arch = "wasm32")]
+use crate::web as backend;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::native as backend;
+
+use crate::OpfsError;
+use futures::{Stream, StreamExt};
+
+pub type Error = OpfsError;
+pub type Result<T> = std::result::Result<T, Error>;
+
+type DirectoryEntry = crate::DirectoryEntry<DirectoryHandle, FileHandle>;
+
+#[derive(Debug, Clone)]
+pub struct DirectoryHandle(backend::DirectoryHandle);
+
+#[derive(Debug, Clone)]
+pub struct FileHandle(backend::FileHandle);
+
+#[derive(Debug)]
+pub struct WritableFileStream(backend::WritableFileStream);
+
+impl crate::private::Sealed for DirectoryHandle {}
+impl crate::private::Sealed for FileHandle {}
+impl crate::private::Sealed for WritableFileStream {}
+
+impl crate::DirectoryHandle for DirectoryHandle {
+    type Error = OpfsError;
+    type FileHandleT = FileHandle;
+
+    async fn get_file_handle_with_options(
+        &self,
+        name: &str,
+        options: &crate::GetFileHandleOptions,
+    ) -> Result<Self::FileHandleT> {
+        self.0
+            .get_file_handle_with_options(name, options)
+            .await
+            .map(FileHandle)
+            .map_err(OpfsError::from)
+    }
+
+    async fn get_directory_handle_with_options(
+        &self,
+        name: &str,
+        options: &crate::GetDirectoryHandleOptions,
+    ) -> Result<Self> {
+        self.0
+            .get_directory_handle_with_options(name, options)
+            .await
+            .map(DirectoryHandle)
+            .map_err(OpfsError::from)
+    }
+
+    async fn open_with_options(
+        &self,
+        name: &str,
+        options: &crate::OpenOptions,
+    ) -> Result<(Self::FileHandleT, WritableFileStream)> {
+        let (file, writable) = self
+            .0
+            .open_with_options(name, options)
+            .await
+            .map_err(OpfsError::from)?;
+        Ok((FileHandle(file), WritableFileStream(writable)))
+    }
+
+    async fn remove_entry(&mut self, name: &str) -> Result<()> {
+        self.0.remove_entry(name).await.map_err(OpfsError::from)
+    }
+
+    async fn remove_entry_with_options(
+        &mut self,
+        name: &str,
+        options: &crate::FileSystemRemoveOptions,
+    ) -> Result<()> {
+        self.0
+            .remove_entry_with_options(name, options)
+            .await
+            .map_err(OpfsError::from)
+    }
+
+    #[allow(clippy::type_complexity)] // not sure how to improve this
+    async fn entries(
+        &self,
+    ) -> Result<impl Stream<Item = Result<(String, DirectoryEntry)>>> {
+        let stream = self.0.entries().await.map_err(OpfsError::from)?;
+        Ok(stream.map(|item| {
+            let (name, entry) = item.map_err(OpfsError::from)?;
+            let entry = match entry {
+                crate::DirectoryEntry::File(file) => crate::DirectoryEntry::File(FileHandle(file)),
+                crate::DirectoryEntry::Directory(dir) => {
+                    crate::DirectoryEntry::Directory(DirectoryHandle(dir))
+                }
+            };
+            Ok((name, entry))
+        }))
+    }
+}
+
+impl crate::FileHandle for FileHandle {
+    type Error = OpfsError;
+    type WritableFileStreamT = WritableFileStream;
+
+    async fn create_writable_with_options(
+        &mut self,
+        options: &crate::CreateWritableOptions,
+    ) -> Result<Self::WritableFileStreamT> {
+        self.0
+            .create_writable_with_options(options)
+            .await
+            .map(WritableFileStream)
+            .map_err(OpfsError::from)
+    }
+
+    async fn read(&self) -> Result<Vec<u8>> {
+        self.0.read().await.map_err(OpfsError::from)
+    }
+
+    async fn read_range<R: std::ops::RangeBounds<usize> + Send>(&self, range: R) -> Result<Vec<u8>> {
+        self.0.read_range(range).await.map_err(OpfsError::from)
+    }
+
+    async fn size(&self) -> Result<usize> {
+        self.0.size().await.map_err(OpfsError::from)
+    }
+
+    async fn read_at(&self, offset: usize, len: usize) -> Result<Vec<u8>> {
+        self.0.read_at(offset, len).await.map_err(OpfsError::from)
+    }
+
+    async fn write_at(&mut self, offset: usize, data: &[u8]) -> Result<()> {
+        self.0.write_at(offset, data).await.map_err(OpfsError::from)
+    }
+}
+
+impl crate::WritableFileStream for WritableFileStream {
+    type Error = OpfsError;
+
+    async fn write_at_cursor_pos(&mut self, data: Vec<u8>) -> Result<()> {
+        self.0.write_at_cursor_pos(data).await.map_err(OpfsError::from)
+    }
+
+    async fn write_with_params(&mut self, params: &crate::WriteParams) -> Result<()> {
+        self.0.write_with_params(params).await.map_err(OpfsError::from)
+    }
+
+    async fn truncate(&mut self, size: usize) -> Result<()> {
+        self.0.truncate(size).await.map_err(OpfsError::from)
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.0.close().await.map_err(OpfsError::from)
+    }
+
+    async fn seek(&mut self, offset: usize) -> Result<()> {
+        self.0.seek(offset).await.map_err(OpfsError::from)
+    }
+}
+
+/// Returns a handle to a directory suitable for persisting this app's data:
+/// the origin private file system on `web`, and the `.opfs-data` folder in the current
+/// working directory on `native`.
+#[cfg(target_arch = "wasm32")]
+pub async fn app_specific_dir() -> Result<DirectoryHandle> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let storage = web_sys::window()
+        .ok_or_else(|| wasm_bindgen::JsValue::from_str("no window"))?
+        .navigator()
+        .storage();
+    let dir_handle: web_sys::FileSystemDirectoryHandle = JsFuture::from(storage.get_directory())
+        .await
+        .map_err(OpfsError::from)?
+        .unchecked_into();
+    Ok(DirectoryHandle(backend::DirectoryHandle::from(dir_handle)))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn app_specific_dir() -> Result<DirectoryHandle> {
+    let path = std::env::current_dir()
+        .map_err(OpfsError::from)?
+        .join(".opfs-data");
+    backend::DirectoryHandle::new(path)
+        .await
+        .map(DirectoryHandle)
+        .map_err(OpfsError::from)
+}
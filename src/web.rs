@@ -1,6 +1,6 @@
 use futures::Stream;
 use futures::StreamExt;
-use js_sys::{ArrayBuffer, AsyncIterator, Uint8Array};
+use js_sys::{ArrayBuffer, AsyncIterator, Reflect, Symbol, Uint8Array};
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::{JsFuture, stream::JsStream};
 use web_sys::{
@@ -8,6 +8,8 @@ use web_sys::{
     FileSystemGetFileOptions, FileSystemRemoveOptions, FileSystemWritableFileStream,
 };
 
+use crate::{FileHandle as _, WritableFileStream as _};
+
 type DirectoryEntry = crate::DirectoryEntry<DirectoryHandle, FileHandle>;
 
 #[derive(Debug, Clone)]
@@ -17,7 +19,13 @@ pub struct DirectoryHandle(FileSystemDirectoryHandle);
 pub struct FileHandle(FileSystemFileHandle);
 
 #[derive(Debug, Clone)]
-pub struct WritableFileStream(FileSystemWritableFileStream);
+pub struct WritableFileStream {
+    inner: FileSystemWritableFileStream,
+    /// Set when this stream was opened with `append: true`: re-queried before every
+    /// `write_at_cursor_pos` so each write lands at the file's current end, not just where it
+    /// happened to end at open time.
+    append_to: Option<FileHandle>,
+}
 
 #[derive(Debug, Clone)]
 pub struct File(web_sys::File);
@@ -36,7 +44,10 @@ impl From<FileSystemFileHandle> for FileHandle {
 
 impl From<FileSystemWritableFileStream> for WritableFileStream {
     fn from(handle: FileSystemWritableFileStream) -> Self {
-        Self(handle)
+        Self {
+            inner: handle,
+            append_to: None,
+        }
     }
 }
 
@@ -67,6 +78,55 @@ impl crate::DirectoryHandle for DirectoryHandle {
         Ok(FileHandle(file_system_file_handle))
     }
 
+    async fn open_with_options(
+        &self,
+        name: &str,
+        options: &crate::OpenOptions,
+    ) -> Result<(Self::FileHandleT, WritableFileStream), Self::Error> {
+        if options.create_new {
+            // There's no native "fail if it exists" option, so probe for existence first with
+            // `create: false` and bail out if that succeeds. Signal it as a `DomException` named
+            // `InvalidModificationError` (the name OPFS itself uses for this condition) so it
+            // round-trips through the `JsValue -> OpfsError` conversion as `AlreadyExists`
+            // instead of falling through to `OpfsError::Backend`.
+            let probe_options = FileSystemGetFileOptions::new();
+            probe_options.set_create(false);
+            if JsFuture::from(self.0.get_file_handle_with_options(name, &probe_options))
+                .await
+                .is_ok()
+            {
+                return Err(web_sys::DomException::new_with_message_and_name(
+                    "entry already exists",
+                    "InvalidModificationError",
+                )?
+                .into());
+            }
+        }
+
+        let fs_options = FileSystemGetFileOptions::new();
+        fs_options.set_create(options.create || options.create_new);
+        let file = FileHandle(FileSystemFileHandle::from(
+            JsFuture::from(self.0.get_file_handle_with_options(name, &fs_options)).await?,
+        ));
+
+        let writable_options = FileSystemCreateWritableOptions::new();
+        writable_options.set_keep_existing_data(!options.truncate);
+        let mut writable: WritableFileStream = FileSystemWritableFileStream::unchecked_from_js(
+            JsFuture::from(file.0.create_writable_with_options(&writable_options)).await?,
+        )
+        .into();
+
+        if options.append {
+            // Position at the current end so the first write lands correctly; every subsequent
+            // `write_at_cursor_pos` re-seeks to the (possibly grown) end on its own.
+            writable.append_to = Some(file.clone());
+            let size = file.size().await?;
+            writable.seek(size).await?;
+        }
+
+        Ok((file, writable))
+    }
+
     async fn get_directory_handle_with_options(
         &self,
         name: &str,
@@ -151,7 +211,7 @@ impl crate::FileHandle for FileHandle {
         let file_system_writable_file_stream = FileSystemWritableFileStream::unchecked_from_js(
             JsFuture::from(self.0.create_writable_with_options(&fs_options)).await?,
         );
-        Ok(WritableFileStream(file_system_writable_file_stream))
+        Ok(file_system_writable_file_stream.into())
     }
 
     async fn read(&self) -> Result<Vec<u8>, Self::Error> {
@@ -170,6 +230,122 @@ impl crate::FileHandle for FileHandle {
         let size = self.get_file().await?.size();
         Ok(size)
     }
+
+    async fn read_at(&self, offset: usize, len: usize) -> Result<Vec<u8>, Self::Error> {
+        self.read_range(offset..offset + len).await
+    }
+
+    /// Overrides the generic default (which drives [`read_at`](crate::FileHandle::read_at), and
+    /// so would re-`get_file()` and slice a fresh `Blob` every chunk) with `Blob::stream()`: the
+    /// underlying `File` is only fetched once, and bytes arrive off its `ReadableStream` via
+    /// [`JsStream`] without OPFS re-reading from the start for each chunk. The stream's own
+    /// chunk boundaries rarely line up with `chunk_size`, so incoming bytes are buffered and
+    /// re-split to the requested size before being yielded.
+    ///
+    /// Async-iterating a `ReadableStream` directly is a newer addition to the spec than OPFS
+    /// itself, so engines that expose OPFS but not `ReadableStream[Symbol.asyncIterator]` are
+    /// still in the wild; on those, fall back to slicing the `Blob` by `chunk_size` ranges
+    /// (still only one `get_file()` call total, just without the zero-copy streaming).
+    async fn read_stream(
+        &self,
+        chunk_size: usize,
+    ) -> Result<impl Stream<Item = Result<Vec<u8>, Self::Error>>, Self::Error>
+    where
+        Self: Clone,
+    {
+        let file = self.get_file().await?;
+        let readable = file.0.stream();
+        let supports_async_iteration =
+            Reflect::has(readable.as_ref(), &Symbol::async_iterator().into()).unwrap_or(false);
+
+        if supports_async_iteration {
+            // The stream is only async-iterable; the iterator with `next()` that `JsStream`
+            // drives comes from calling its `[Symbol.asyncIterator]` method.
+            let iterator_fn: js_sys::Function =
+                Reflect::get(readable.as_ref(), &Symbol::async_iterator().into())?.unchecked_into();
+            let async_iterator = AsyncIterator::from(iterator_fn.call0(readable.as_ref())?);
+            let js_stream = JsStream::from(async_iterator);
+
+            Ok(futures::stream::unfold(
+                (Box::pin(js_stream), Vec::<u8>::new(), chunk_size),
+                move |(mut inner, mut buf, chunk_size)| async move {
+                    loop {
+                        if buf.len() >= chunk_size {
+                            let tail = buf.split_off(chunk_size);
+                            let chunk = std::mem::replace(&mut buf, tail);
+                            return Some((Ok(chunk), (inner, buf, chunk_size)));
+                        }
+                        match inner.next().await {
+                            Some(Ok(value)) => {
+                                let array = Uint8Array::new(&value);
+                                let mut bytes = vec![0u8; array.length() as usize];
+                                array.copy_to(&mut bytes);
+                                buf.extend(bytes);
+                            }
+                            Some(Err(e)) => {
+                                return Some((Err(e), (inner, Vec::new(), chunk_size)));
+                            }
+                            None => {
+                                if buf.is_empty() {
+                                    return None;
+                                }
+                                let chunk = std::mem::take(&mut buf);
+                                return Some((Ok(chunk), (inner, Vec::new(), chunk_size)));
+                            }
+                        }
+                    }
+                },
+            )
+            .left_stream())
+        } else {
+            let size = file.size();
+            Ok(futures::stream::unfold(
+                (file, 0usize, size, chunk_size),
+                move |(file, offset, size, chunk_size)| async move {
+                    if offset >= size {
+                        return None;
+                    }
+                    let len = chunk_size.min(size - offset);
+                    match file.read_range(offset..offset + len).await {
+                        Ok(chunk) => Some((Ok(chunk), (file, offset + len, size, chunk_size))),
+                        Err(e) => Some((Err(e), (file, size, size, chunk_size))),
+                    }
+                },
+            )
+            .right_stream())
+        }
+    }
+
+    async fn write_at(&mut self, offset: usize, data: &[u8]) -> Result<(), Self::Error> {
+        // Opening and closing a WritableFileStream per call is heavier than we'd like, but it's
+        // the only way to get positioned writes without buffering the whole file - see
+        // `write_with_params`'s Seek/Write commands.
+        let mut writable = self
+            .create_writable_with_options(&crate::CreateWritableOptions {
+                keep_existing_data: true,
+            })
+            .await?;
+
+        writable
+            .write_with_params(&crate::WriteParams {
+                command_type: crate::WriteCommandType::Seek,
+                data: None,
+                position: Some(offset),
+                size: None,
+            })
+            .await?;
+
+        writable
+            .write_with_params(&crate::WriteParams {
+                command_type: crate::WriteCommandType::Write,
+                data: Some(data.to_vec()),
+                position: None,
+                size: None,
+            })
+            .await?;
+
+        writable.close().await
+    }
 }
 
 impl FileHandle {
@@ -190,12 +366,20 @@ impl crate::WritableFileStream for WritableFileStream {
         // But a safari bug makes this write basically the entire wasm heap to the file.
         // So we have to write as a File first.
 
+        if let Some(file) = &self.append_to {
+            // Append mode: re-seek to the current end before every write, since something else
+            // (another handle, a prior write_with_params call) may have grown the file since we
+            // last positioned the cursor.
+            let size = file.size().await?;
+            self.seek(size).await?;
+        }
+
         let uint8_array = js_sys::Uint8Array::from(data.as_slice());
         let array = js_sys::Array::new();
         array.push(&uint8_array);
         let file = web_sys::File::new_with_u8_array_sequence(&array, "filename")?;
 
-        JsFuture::from(self.0.write_with_blob(&file)?).await?;
+        JsFuture::from(self.inner.write_with_blob(&file)?).await?;
         Ok(())
     }
 
@@ -228,22 +412,22 @@ impl crate::WritableFileStream for WritableFileStream {
             web_params.set_size(Some(size as f64));
         }
 
-        JsFuture::from(self.0.write_with_write_params(&web_params)?).await?;
+        JsFuture::from(self.inner.write_with_write_params(&web_params)?).await?;
         Ok(())
     }
 
     async fn truncate(&mut self, size: usize) -> Result<(), Self::Error> {
-        JsFuture::from(self.0.truncate_with_u32(size as u32)?).await?;
+        JsFuture::from(self.inner.truncate_with_u32(size as u32)?).await?;
         Ok(())
     }
 
     async fn close(&mut self) -> Result<(), Self::Error> {
-        JsFuture::from(self.0.close()).await?;
+        JsFuture::from(self.inner.close()).await?;
         Ok(())
     }
 
     async fn seek(&mut self, offset: usize) -> Result<(), Self::Error> {
-        JsFuture::from(self.0.seek_with_u32(offset as u32)?).await?;
+        JsFuture::from(self.inner.seek_with_u32(offset as u32)?).await?;
         Ok(())
     }
 }